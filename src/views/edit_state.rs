@@ -0,0 +1,229 @@
+use std::rc::Rc;
+
+/// Shared text-buffer plumbing for [`EditView`] and [`TextArea`]: the
+/// content and cursor, the undo/redo history, the kill ring, and the
+/// character filter.
+///
+/// [`EditView`]: struct.EditView.html
+/// [`TextArea`]: struct.TextArea.html
+pub struct EditState {
+    /// Current content.
+    pub(crate) content: Rc<String>,
+    /// Cursor position in the content, in bytes.
+    pub(crate) cursor: usize,
+
+    /// Callback used to filter or transform incoming characters.
+    ///
+    /// Called with the character the user just typed; if it returns
+    /// `None`, the character is dropped, otherwise the returned
+    /// character is inserted instead.
+    pub(crate) filter: Option<Rc<Fn(char) -> Option<char>>>,
+
+    /// Text removed by the last kill operation (Ctrl-K/Ctrl-U/Ctrl-W),
+    /// ready to be yanked back with Ctrl-Y.
+    pub(crate) kill_buffer: String,
+
+    /// `true` right after a Ctrl-K, so a following Ctrl-K appends to
+    /// `kill_buffer` instead of overwriting it.
+    pub(crate) continuing_kill: bool,
+
+    /// Snapshots of `(content, cursor)` taken before each edit, for undo.
+    undo_stack: Vec<(Rc<String>, usize)>,
+
+    /// Snapshots popped off `undo_stack`, for redo.
+    redo_stack: Vec<(Rc<String>, usize)>,
+
+    /// `true` right after a typing (non-whitespace) insertion, so the
+    /// next one coalesces into the same undo group.
+    typing_run: bool,
+
+    /// Maximum number of snapshots kept in `undo_stack`, if any.
+    max_undo_depth: Option<usize>,
+}
+
+impl EditState {
+    /// Creates a new, empty state.
+    pub fn new() -> Self {
+        EditState {
+            content: Rc::new(String::new()),
+            cursor: 0,
+            filter: None,
+            kill_buffer: String::new(),
+            continuing_kill: false,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            typing_run: false,
+            max_undo_depth: None,
+        }
+    }
+
+    /// Sets the maximum number of undo snapshots to keep.
+    ///
+    /// Older snapshots are dropped once this is exceeded.
+    pub fn set_max_undo_depth(&mut self, max_undo_depth: usize) {
+        self.max_undo_depth = Some(max_undo_depth);
+    }
+
+    /// Records `(content, cursor)` onto the undo stack before an edit.
+    ///
+    /// `coalesce` should be `true` for single-character typing, so a run
+    /// of ordinary keystrokes collapses into one undo group; any other
+    /// edit (deletion, paste, ...) always starts a fresh group.
+    pub(crate) fn snapshot(&mut self, coalesce: bool) {
+        if !(coalesce && self.typing_run) {
+            self.undo_stack.push((self.content.clone(), self.cursor));
+            if let Some(max_undo_depth) = self.max_undo_depth {
+                while self.undo_stack.len() > max_undo_depth {
+                    self.undo_stack.remove(0);
+                }
+            }
+        }
+        self.redo_stack.clear();
+        self.typing_run = coalesce;
+    }
+
+    /// Undoes the last edit, restoring the previous content and cursor.
+    pub fn undo(&mut self) {
+        if let Some((content, cursor)) = self.undo_stack.pop() {
+            self.redo_stack.push((self.content.clone(), self.cursor));
+            self.content = content;
+            self.cursor = cursor;
+            self.typing_run = false;
+        }
+    }
+
+    /// Redoes the last undone edit.
+    pub fn redo(&mut self) {
+        if let Some((content, cursor)) = self.redo_stack.pop() {
+            self.undo_stack.push((self.content.clone(), self.cursor));
+            self.content = content;
+            self.cursor = cursor;
+            self.typing_run = false;
+        }
+    }
+
+    /// Insert `ch` at the current cursor position, without snapshotting.
+    fn insert_raw(&mut self, ch: char) {
+        // `make_mut` applies copy-on-write
+        // It means it'll just return a ref if no one else has a ref,
+        // and it will clone it into `self.content` otherwise.
+        Rc::make_mut(&mut self.content).insert(self.cursor, ch);
+        self.cursor += ch.len_utf8();
+    }
+
+    /// Removes `len` bytes starting at the current cursor position,
+    /// without snapshotting.
+    fn remove_raw(&mut self, len: usize) {
+        let start = self.cursor;
+        let end = self.cursor + len;
+        for _ in Rc::make_mut(&mut self.content).drain(start..end) {}
+    }
+
+    /// Insert `ch` at the current cursor position.
+    pub fn insert(&mut self, ch: char) {
+        self.snapshot(!ch.is_whitespace());
+        self.insert_raw(ch);
+    }
+
+    /// Remove `len` bytes starting at the current cursor position.
+    pub fn remove(&mut self, len: usize) {
+        self.snapshot(false);
+        self.remove_raw(len);
+    }
+
+    /// Removes `len` bytes at the cursor, then inserts `ch` in their
+    /// place, as a single undo group (for overwrite-mode typing).
+    pub fn replace(&mut self, len: usize, ch: char) {
+        self.snapshot(!ch.is_whitespace());
+        self.remove_raw(len);
+        self.insert_raw(ch);
+    }
+
+    /// Inserts `text` at the cursor as a single undo group (for Ctrl-Y
+    /// yank), instead of one group per character.
+    pub fn insert_str(&mut self, text: &str) {
+        self.snapshot(false);
+        for ch in text.chars() {
+            self.insert_raw(ch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consecutive_typing_coalesces_into_one_undo_group() {
+        let mut state = EditState::new();
+        state.insert('a');
+        state.insert('b');
+        state.insert('c');
+
+        assert_eq!(&*state.content, "abc");
+        state.undo();
+        assert_eq!(&*state.content, "");
+    }
+
+    #[test]
+    fn whitespace_breaks_the_typing_run() {
+        let mut state = EditState::new();
+        state.insert('a');
+        state.insert(' ');
+        state.insert('b');
+
+        assert_eq!(&*state.content, "a b");
+        state.undo();
+        assert_eq!(&*state.content, "a ");
+        state.undo();
+        assert_eq!(&*state.content, "a");
+        state.undo();
+        assert_eq!(&*state.content, "");
+    }
+
+    #[test]
+    fn non_typing_edit_starts_a_fresh_group() {
+        let mut state = EditState::new();
+        state.insert('a');
+        state.insert('b');
+        // remove() deletes forward from the cursor, like every real
+        // call site (which repositions the cursor first); back up over
+        // the 'b' just typed before removing it.
+        state.cursor -= 1;
+        state.remove(1);
+
+        assert_eq!(&*state.content, "a");
+        state.undo();
+        assert_eq!(&*state.content, "ab");
+        state.undo();
+        assert_eq!(&*state.content, "");
+    }
+
+    #[test]
+    fn redo_replays_an_undone_group() {
+        let mut state = EditState::new();
+        state.insert('a');
+        state.insert('b');
+        state.undo();
+        assert_eq!(&*state.content, "");
+        state.redo();
+        assert_eq!(&*state.content, "ab");
+    }
+
+    #[test]
+    fn max_undo_depth_bounds_the_stack() {
+        let mut state = EditState::new();
+        state.set_max_undo_depth(1);
+        state.insert('a');
+        state.insert(' ');
+        state.insert('b');
+        state.insert(' ');
+        state.insert('c');
+
+        // Only the most recent group can be undone.
+        state.undo();
+        assert_eq!(&*state.content, "a b ");
+        state.undo();
+        assert_eq!(&*state.content, "a b ");
+    }
+}
@@ -10,6 +10,7 @@ use vec::Vec2;
 use view::View;
 use event::{Callback, Event, EventResult, Key};
 use utils::simple_suffix_length;
+use views::edit_state::EditState;
 
 
 /// Input box where the user can enter and edit text.
@@ -57,10 +58,10 @@ use utils::simple_suffix_length;
 /// # }
 /// ```
 pub struct EditView {
-    /// Current content.
-    content: Rc<String>,
-    /// Cursor position in the content, in bytes.
-    cursor: usize,
+    /// Content, cursor, undo/redo history, kill ring and filter, shared
+    /// with `TextArea`.
+    state: EditState,
+
     /// Minimum layout length asked to the parent.
     min_length: usize,
 
@@ -82,6 +83,21 @@ pub struct EditView {
     /// When `true`, only print `*` instead of the true content.
     secret: bool,
 
+    /// When `true`, typed characters replace the grapheme under the
+    /// cursor instead of being inserted before it.
+    overwrite: bool,
+
+    /// Provides completion candidates for the current content and cursor.
+    completer: Option<Rc<Fn(&str, usize) -> Vec<String>>>,
+
+    /// Called on `<Tab>` when several candidates share no longer common
+    /// prefix, so the host app can show them (e.g. in a popup).
+    on_ambiguous_complete: Option<Rc<Fn(&mut Cursive, &[String])>>,
+
+    /// Top completion candidate, shown as a dimmed inline suggestion
+    /// after the cursor when it sits at the end of the content.
+    suggestion: Option<String>,
+
     enabled: bool,
 }
 
@@ -91,14 +107,17 @@ impl EditView {
     /// Creates a new, empty edit view.
     pub fn new() -> Self {
         EditView {
-            content: Rc::new(String::new()),
-            cursor: 0,
+            state: EditState::new(),
             offset: 0,
             min_length: 1,
             last_length: 0, // scrollable: false,
             on_edit: None,
             on_submit: None,
             secret: false,
+            overwrite: false,
+            completer: None,
+            on_ambiguous_complete: None,
+            suggestion: None,
             enabled: true,
         }
     }
@@ -117,6 +136,19 @@ impl EditView {
         self.with(|s| s.set_secret(true))
     }
 
+    /// If `overwrite` is `true`, typed characters replace the grapheme
+    /// under the cursor instead of being inserted before it.
+    pub fn set_overwrite(&mut self, overwrite: bool) {
+        self.overwrite = overwrite;
+    }
+
+    /// Starts this view in overwrite mode.
+    ///
+    /// Chainable variant of [`set_overwrite`](#method.set_overwrite).
+    pub fn overwrite(self) -> Self {
+        self.with(|s| s.set_overwrite(true))
+    }
+
     /// Disables this view.
     ///
     /// A disabled view cannot be selected.
@@ -157,6 +189,31 @@ impl EditView {
         self
     }
 
+    /// Sets a callback providing completion candidates for `<Tab>`.
+    ///
+    /// `completer` is given the current content and cursor position, and
+    /// returns the list of matching candidates, most relevant first.
+    pub fn on_complete<F: Fn(&str, usize) -> Vec<String> + 'static>
+        (mut self,
+         completer: F)
+         -> Self {
+        self.completer = Some(Rc::new(completer));
+        self
+    }
+
+    /// Sets a callback invoked on `<Tab>` when completion is ambiguous.
+    ///
+    /// Called with every candidate when more than one matches and they
+    /// share no prefix longer than what's already typed, so the host
+    /// app can show them (e.g. in a popup).
+    pub fn on_ambiguous_complete<F: Fn(&mut Cursive, &[String]) + 'static>
+        (mut self,
+         callback: F)
+         -> Self {
+        self.on_ambiguous_complete = Some(Rc::new(callback));
+        self
+    }
+
     /// Enable or disable this view.
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
@@ -170,12 +227,12 @@ impl EditView {
     /// Replace the entire content of the view with the given one.
     pub fn set_content(&mut self, content: &str) {
         self.offset = 0;
-        self.content = Rc::new(content.to_string());
+        self.state.content = Rc::new(content.to_string());
     }
 
     /// Get the current text.
     pub fn get_content(&self) -> Rc<String> {
-        self.content.clone()
+        self.state.content.clone()
     }
 
     /// Sets the current content to the given value.
@@ -194,20 +251,100 @@ impl EditView {
         self
     }
 
+    /// Sets a filter to apply to each character before it is inserted.
+    ///
+    /// `filter` is called with the character the user just typed.
+    /// Returning `None` drops the keystroke entirely (the content and
+    /// cursor are left untouched); returning `Some(c)` inserts `c`
+    /// instead. This can be used to restrict input to digits, force a
+    /// case, or remap keys, without post-hoc validation in `on_edit`.
+    pub fn filter_char<F: Fn(char) -> Option<char> + 'static>(mut self,
+                                                               filter: F)
+                                                               -> Self {
+        self.state.filter = Some(Rc::new(filter));
+        self
+    }
+
+    /// Sets the maximum number of undo snapshots to keep.
+    ///
+    /// Older snapshots are dropped once this is exceeded. Without a call
+    /// to this method, the history is unbounded.
+    pub fn max_undo_depth(mut self, max_undo_depth: usize) -> Self {
+        self.state.set_max_undo_depth(max_undo_depth);
+        self
+    }
+
+    /// Undoes the last edit, restoring the previous content and cursor.
+    pub fn undo(&mut self) {
+        self.state.undo();
+    }
+
+    /// Redoes the last undone edit.
+    pub fn redo(&mut self) {
+        self.state.redo();
+    }
+
     /// Insert `ch` at the current cursor position.
     pub fn insert(&mut self, ch: char) {
-        // `make_mut` applies copy-on-write
-        // It means it'll just return a ref if no one else has a ref,
-        // and it will clone it into `self.content` otherwise.
-        Rc::make_mut(&mut self.content).insert(self.cursor, ch);
-        self.cursor += ch.len_utf8();
+        self.state.insert(ch);
     }
 
     /// Remove the character at the current cursor position.
     pub fn remove(&mut self, len: usize) {
-        let start = self.cursor;
-        let end = self.cursor + len;
-        for _ in Rc::make_mut(&mut self.content).drain(start..end) {}
+        self.state.remove(len);
+    }
+}
+
+/// Finds the byte offset of the start of the word following `cursor`.
+///
+/// Skips the remainder of the current word (if any) and any following
+/// whitespace/punctuation, stopping at the beginning of the next word.
+/// Returns `content.len()` if there is no following word.
+fn word_start_after(content: &str, cursor: usize) -> usize {
+    for (offset, word) in content.split_word_bound_indices() {
+        if offset <= cursor || !word.chars().any(char::is_alphanumeric) {
+            continue;
+        }
+        return offset;
+    }
+    content.len()
+}
+
+/// Finds the byte offset of the start of the word preceding `cursor`.
+///
+/// Skips any whitespace/punctuation immediately before `cursor`, then
+/// returns the beginning of the previous word. Returns `0` if there is
+/// no preceding word.
+fn word_start_before(content: &str, cursor: usize) -> usize {
+    let mut start = 0;
+    for (offset, word) in content.split_word_bound_indices() {
+        if offset >= cursor {
+            break;
+        }
+        if word.chars().any(char::is_alphanumeric) {
+            start = offset;
+        }
+    }
+    start
+}
+
+/// Returns the longest prefix shared by every string in `candidates`.
+fn common_prefix(candidates: &[String]) -> String {
+    let mut iters: Vec<_> = candidates.iter().map(|s| s.chars()).collect();
+    let mut prefix = String::new();
+    loop {
+        let mut next = None;
+        for it in &mut iters {
+            match (it.next(), next) {
+                (Some(c), None) => next = Some(c),
+                (Some(c), Some(expected)) if c == expected => (),
+                _ => return prefix,
+            }
+        }
+        match next {
+            Some(c) => prefix.push(c),
+            None => return prefix,
+        }
     }
 }
 
@@ -226,7 +363,7 @@ impl View for EditView {
                 self.last_length,
                 printer.size.x);
 
-        let width = self.content.width();
+        let width = self.state.content.width();
         printer.with_color(ColorStyle::Secondary, |printer| {
             let effect = if self.enabled {
                 Effect::Reverse
@@ -239,13 +376,13 @@ impl View for EditView {
                     if self.secret {
                         printer.print_hline((0, 0), width, "*");
                     } else {
-                        printer.print((0, 0), &self.content);
+                        printer.print((0, 0), &self.state.content);
                     }
                     printer.print_hline((width, 0),
                                         printer.size.x - width,
                                         "_");
                 } else {
-                    let content = &self.content[self.offset..];
+                    let content = &self.state.content[self.offset..];
                     let display_bytes = content.graphemes(true)
                         .scan(0, |w, g| {
                             *w += g.width();
@@ -277,24 +414,48 @@ impl View for EditView {
 
             // Now print cursor
             if printer.focused {
-                let c: &str = if self.cursor == self.content.len() {
+                let c: &str = if self.state.cursor == self.state.content.len() {
                     "_"
+                } else if self.overwrite {
+                    // A full-cell block tells the user overwrite mode
+                    // is on, instead of highlighting the next char.
+                    "█"
                 } else {
                     // Get the char from the string... Is it so hard?
-                    let selected = self.content[self.cursor..]
+                    let selected = self.state.content[self.state.cursor..]
                         .graphemes(true)
                         .next()
                         .expect(&format!("Found no char at cursor {} in {}",
-                                         self.cursor,
-                                         &self.content));
+                                         self.state.cursor,
+                                         &self.state.content));
                     if self.secret {
                         make_small_stars(selected.width())
                     } else {
                         selected
                     }
                 };
-                let offset = self.content[self.offset..self.cursor].width();
+                let offset = self.state.content[self.offset..self.state.cursor].width();
                 printer.print((offset, 0), c);
+
+                // Ghost suggestion: the unmatched suffix of the top
+                // completion candidate, dimmed, shown only at EOL.
+                if !self.secret && self.state.cursor == self.state.content.len() {
+                    if let Some(ref suggestion) = self.suggestion {
+                        if suggestion.starts_with(&*self.state.content) {
+                            let content_width = self.state.content.width();
+                            let suffix = &suggestion[self.state.content.len()..];
+                            let suffix_width = suffix.width();
+                            if content_width + suffix_width <=
+                               self.last_length {
+                                printer.with_color(ColorStyle::Tertiary,
+                                                    |printer| {
+                                    printer.print((content_width, 0),
+                                                   suffix);
+                                });
+                            }
+                        }
+                    }
+                }
             }
         });
     }
@@ -312,43 +473,190 @@ impl View for EditView {
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
+        // Only a Ctrl-K immediately following another Ctrl-K extends the
+        // kill buffer; any other event breaks the chain.
+        let extend_kill = self.state.continuing_kill;
+        self.state.continuing_kill = false;
 
         match event {
             Event::Char(ch) => {
-                // Find the byte index of the char at self.cursor
+                // Find the byte index of the char at self.state.cursor
+
+                let ch = match self.state.filter {
+                    None => Some(ch),
+                    Some(ref filter) => filter(ch),
+                };
 
-                self.insert(ch);
+                match ch {
+                    None => return EventResult::Consumed(None),
+                    Some(ch) => {
+                        if self.overwrite && self.state.cursor < self.state.content.len() {
+                            let len = self.state.content[self.state.cursor..]
+                                .graphemes(true)
+                                .next()
+                                .unwrap()
+                                .len();
+                            // Remove and insert as a single undo group,
+                            // instead of two (one per call).
+                            self.state.replace(len, ch);
+                        } else {
+                            self.insert(ch);
+                        }
+                    }
+                }
+            }
+            Event::Key(Key::Ins) => {
+                self.overwrite = !self.overwrite;
+            }
+            Event::CtrlChar('a') => self.state.cursor = 0,
+            Event::CtrlChar('e') => self.state.cursor = self.state.content.len(),
+            Event::CtrlChar('b') if self.state.cursor > 0 => {
+                let len = self.state.content[..self.state.cursor]
+                    .graphemes(true)
+                    .last()
+                    .unwrap()
+                    .len();
+                self.state.cursor -= len;
+            }
+            Event::CtrlChar('f') if self.state.cursor < self.state.content.len() => {
+                let len = self.state.content[self.state.cursor..]
+                    .graphemes(true)
+                    .next()
+                    .unwrap()
+                    .len();
+                self.state.cursor += len;
             }
-            // TODO: handle ctrl-key?
-            Event::Key(Key::Home) => self.cursor = 0,
-            Event::Key(Key::End) => self.cursor = self.content.len(),
-            Event::Key(Key::Left) if self.cursor > 0 => {
-                let len = self.content[..self.cursor]
+            Event::CtrlChar('k') => {
+                let killed = self.state.content[self.state.cursor..].to_string();
+                if extend_kill {
+                    self.state.kill_buffer.push_str(&killed);
+                } else {
+                    self.state.kill_buffer = killed;
+                }
+                let len = self.state.content.len() - self.state.cursor;
+                self.remove(len);
+                self.state.continuing_kill = true;
+            }
+            Event::CtrlChar('u') => {
+                self.state.kill_buffer = self.state.content[..self.state.cursor].to_string();
+                let len = self.state.cursor;
+                self.state.cursor = 0;
+                self.remove(len);
+            }
+            Event::CtrlChar('w') => {
+                let before = &self.state.content[..self.state.cursor];
+                let mut word_start = self.state.cursor;
+                let mut seen_word = false;
+                for word in before.split_word_bounds().rev() {
+                    if word.trim().is_empty() {
+                        if seen_word {
+                            break;
+                        }
+                    } else {
+                        seen_word = true;
+                    }
+                    word_start -= word.len();
+                }
+                let len = self.state.cursor - word_start;
+                self.state.kill_buffer = self.state.content[word_start..self.state.cursor]
+                    .to_string();
+                self.state.cursor = word_start;
+                self.remove(len);
+            }
+            Event::CtrlChar('y') => {
+                let text = self.state.kill_buffer.clone();
+                self.state.insert_str(&text);
+            }
+            // Ctrl-Z can't be used here: the curses backend only maps
+            // codes 1-25 to CtrlChar('a')..('y'), so code 26 (real
+            // Ctrl-Z) never reaches us, and CtrlChar can't carry an
+            // uppercase letter for a Ctrl-Shift-Z either. Alt-U/Alt-R
+            // are both real, reachable bindings.
+            Event::AltChar('u') => self.undo(),
+            Event::AltChar('r') => self.redo(),
+            Event::Key(Key::Tab) if self.completer.is_some() => {
+                let completer = self.completer.clone().unwrap();
+                let candidates = completer(&self.state.content, self.state.cursor);
+                match candidates.len() {
+                    0 => (),
+                    1 => {
+                        self.state.snapshot(false);
+                        self.set_content(&candidates[0]);
+                        self.state.cursor = self.state.content.len();
+                    }
+                    _ => {
+                        let prefix = common_prefix(&candidates);
+                        if prefix.len() > self.state.cursor {
+                            self.state.snapshot(false);
+                            self.set_content(&prefix);
+                            self.state.cursor = self.state.content.len();
+                        } else if let Some(ref on_ambiguous_complete) =
+                            self.on_ambiguous_complete {
+                            let cb = on_ambiguous_complete.clone();
+                            return EventResult::with_cb(move |s| {
+                                cb(s, &candidates);
+                            });
+                        }
+                    }
+                }
+            }
+            Event::Key(Key::Right) if self.state.cursor == self.state.content.len() &&
+                                       self.suggestion.is_some() => {
+                let suggestion = self.suggestion.take().unwrap();
+                self.state.snapshot(false);
+                self.set_content(&suggestion);
+                self.state.cursor = self.state.content.len();
+            }
+            Event::Key(Key::End) if self.state.cursor == self.state.content.len() &&
+                                     self.suggestion.is_some() => {
+                let suggestion = self.suggestion.take().unwrap();
+                self.state.snapshot(false);
+                self.set_content(&suggestion);
+                self.state.cursor = self.state.content.len();
+            }
+            Event::Key(Key::Home) => self.state.cursor = 0,
+            Event::Key(Key::End) => self.state.cursor = self.state.content.len(),
+            Event::Key(Key::Left) if self.state.cursor > 0 => {
+                let len = self.state.content[..self.state.cursor]
                     .graphemes(true)
                     .last()
                     .unwrap()
                     .len();
-                self.cursor -= len;
+                self.state.cursor -= len;
             }
-            Event::Key(Key::Right) if self.cursor < self.content.len() => {
-                let len = self.content[self.cursor..]
+            Event::Key(Key::Right) if self.state.cursor < self.state.content.len() => {
+                let len = self.state.content[self.state.cursor..]
                     .graphemes(true)
                     .next()
                     .unwrap()
                     .len();
-                self.cursor += len;
+                self.state.cursor += len;
             }
-            Event::Key(Key::Backspace) if self.cursor > 0 => {
-                let len = self.content[..self.cursor]
+            Event::Key(Key::Backspace) if self.state.cursor > 0 => {
+                let len = self.state.content[..self.state.cursor]
                     .graphemes(true)
                     .last()
                     .unwrap()
                     .len();
-                self.cursor -= len;
+                self.state.cursor -= len;
+                self.remove(len);
+            }
+            Event::Ctrl(Key::Right) |
+            Event::AltChar('f') if self.state.cursor < self.state.content.len() => {
+                self.state.cursor = word_start_after(&self.state.content, self.state.cursor);
+            }
+            Event::Ctrl(Key::Left) |
+            Event::AltChar('b') if self.state.cursor > 0 => {
+                self.state.cursor = word_start_before(&self.state.content, self.state.cursor);
+            }
+            Event::Ctrl(Key::Backspace) if self.state.cursor > 0 => {
+                let new_cursor = word_start_before(&self.state.content, self.state.cursor);
+                let len = self.state.cursor - new_cursor;
+                self.state.cursor = new_cursor;
                 self.remove(len);
             }
-            Event::Key(Key::Del) if self.cursor < self.content.len() => {
-                let len = self.content[self.cursor..]
+            Event::Key(Key::Del) if self.state.cursor < self.state.content.len() => {
+                let len = self.state.content[self.state.cursor..]
                     .graphemes(true)
                     .next()
                     .unwrap()
@@ -357,7 +665,7 @@ impl View for EditView {
             }
             Event::Key(Key::Enter) if self.on_submit.is_some() => {
                 let cb = self.on_submit.clone().unwrap();
-                let content = self.content.clone();
+                let content = self.state.content.clone();
                 return EventResult::with_cb(move |s| {
                     cb(s, &content);
                 });
@@ -369,43 +677,50 @@ impl View for EditView {
         // So keep offset in [last_length-cursor,cursor]
         // Also call this on resize,
         // but right now it is an event like any other
-        if self.cursor < self.offset {
-            self.offset = self.cursor;
+        if self.state.cursor < self.offset {
+            self.offset = self.state.cursor;
         } else {
             // So we're against the right wall.
             // Let's find how much space will be taken by the selection
             // (either a char, or _)
-            let c_len = self.content[self.cursor..]
+            let c_len = self.state.content[self.state.cursor..]
                 .graphemes(true)
                 .map(|g| g.width())
                 .next()
                 .unwrap_or(1);
-            // Now, we have to fit self.content[..self.cursor]
+            // Now, we have to fit self.state.content[..self.state.cursor]
             // into self.last_length - c_len.
             let available = self.last_length - c_len;
             // Look at the content before the cursor (we will print its tail).
             // From the end, count the length until we reach `available`.
             // Then sum the byte lengths.
             let suffix_length =
-                simple_suffix_length(&self.content[self.offset..self.cursor],
+                simple_suffix_length(&self.state.content[self.offset..self.state.cursor],
                                      available);
-            self.offset = self.cursor - suffix_length;
-            assert!(self.cursor >= self.offset);
+            self.offset = self.state.cursor - suffix_length;
+            assert!(self.state.cursor >= self.offset);
 
         }
 
         // If we have too much space
-        if self.content[self.offset..].width() < self.last_length {
-            let suffix_length = simple_suffix_length(&self.content,
+        if self.state.content[self.offset..].width() < self.last_length {
+            let suffix_length = simple_suffix_length(&self.state.content,
                                                      self.last_length - 1);
-            self.offset = self.content.len() - suffix_length;
+            self.offset = self.state.content.len() - suffix_length;
         }
 
+        // Refresh the ghost suggestion for the new content/cursor.
+        self.suggestion = self.completer
+            .clone()
+            .and_then(|completer| completer(&self.state.content, self.state.cursor)
+                .into_iter()
+                .next());
+
         let cb = self.on_edit.clone().map(|cb| {
 
             // Get a new Rc on it
-            let content = self.content.clone();
-            let cursor = self.cursor;
+            let content = self.state.content.clone();
+            let cursor = self.state.cursor;
 
             Callback::from_fn(move |s| {
                 cb(s, &content, cursor);
@@ -414,3 +729,154 @@ impl View for EditView {
         EventResult::Consumed(cb)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::{Event, Key};
+
+    #[test]
+    fn word_start_after_skips_to_the_next_word() {
+        assert_eq!(word_start_after("foo bar baz", 0), 4);
+        assert_eq!(word_start_after("foo bar baz", 2), 4);
+        assert_eq!(word_start_after("foo bar baz", 4), 8);
+        assert_eq!(word_start_after("foo bar baz", 11), 11);
+    }
+
+    #[test]
+    fn word_start_before_skips_to_the_previous_word() {
+        assert_eq!(word_start_before("foo bar baz", 11), 8);
+        assert_eq!(word_start_before("foo bar baz", 9), 8);
+        assert_eq!(word_start_before("foo bar baz", 4), 0);
+        assert_eq!(word_start_before("foo bar baz", 0), 0);
+    }
+
+    #[test]
+    fn common_prefix_of_shared_candidates() {
+        let candidates = vec!["hello".to_string(), "help".to_string(), "helm".to_string()];
+        assert_eq!(common_prefix(&candidates), "hel");
+    }
+
+    #[test]
+    fn common_prefix_with_no_overlap_is_empty() {
+        let candidates = vec!["foo".to_string(), "bar".to_string()];
+        assert_eq!(common_prefix(&candidates), "");
+    }
+
+    #[test]
+    fn common_prefix_of_a_single_candidate_is_itself() {
+        let candidates = vec!["solo".to_string()];
+        assert_eq!(common_prefix(&candidates), "solo");
+    }
+
+    #[test]
+    fn tab_with_a_single_candidate_completes_and_moves_to_end() {
+        let mut view = EditView::new()
+            .content("hel")
+            .on_complete(|_, _| vec!["hello".to_string()]);
+        view.layout(Vec2::new(20, 1));
+        view.state.cursor = 3;
+
+        view.on_event(Event::Key(Key::Tab));
+
+        assert_eq!(&*view.get_content(), "hello");
+        assert_eq!(view.state.cursor, 5);
+    }
+
+    #[test]
+    fn tab_with_ambiguous_candidates_extends_to_their_common_prefix() {
+        let mut view = EditView::new()
+            .content("h")
+            .on_complete(|_, _| vec!["hello".to_string(), "help".to_string()]);
+        view.layout(Vec2::new(20, 1));
+        view.state.cursor = 1;
+
+        view.on_event(Event::Key(Key::Tab));
+
+        assert_eq!(&*view.get_content(), "hel");
+        assert_eq!(view.state.cursor, 3);
+    }
+
+    #[test]
+    fn ctrl_right_moves_by_whole_word() {
+        let mut view = EditView::new().content("foo bar baz");
+        view.layout(Vec2::new(20, 1));
+        view.state.cursor = 0;
+
+        view.on_event(Event::Ctrl(Key::Right));
+
+        assert_eq!(view.state.cursor, 4);
+    }
+
+    #[test]
+    fn ctrl_backspace_deletes_the_previous_word() {
+        let mut view = EditView::new().content("foo bar baz");
+        view.layout(Vec2::new(20, 1));
+        view.state.cursor = view.get_content().len();
+
+        view.on_event(Event::Ctrl(Key::Backspace));
+
+        assert_eq!(&*view.get_content(), "foo bar ");
+    }
+
+    #[test]
+    fn ctrl_k_then_ctrl_u_kill_to_opposite_ends() {
+        let mut view = EditView::new().content("hello world");
+        view.layout(Vec2::new(20, 1));
+        view.state.cursor = 5;
+
+        view.on_event(Event::CtrlChar('k'));
+        assert_eq!(&*view.get_content(), "hello");
+        assert_eq!(view.state.kill_buffer, " world");
+
+        view.state.cursor = 2;
+        view.on_event(Event::CtrlChar('u'));
+        assert_eq!(&*view.get_content(), "llo");
+        assert_eq!(view.state.kill_buffer, "he");
+    }
+
+    #[test]
+    fn ctrl_w_kills_the_previous_word() {
+        let mut view = EditView::new().content("hello world");
+        view.layout(Vec2::new(20, 1));
+        view.state.cursor = view.get_content().len();
+
+        view.on_event(Event::CtrlChar('w'));
+
+        assert_eq!(&*view.get_content(), "hello ");
+        assert_eq!(view.state.kill_buffer, "world");
+    }
+
+    #[test]
+    fn ctrl_y_yanks_the_kill_buffer_at_the_cursor() {
+        let mut view = EditView::new().content("hello world");
+        view.layout(Vec2::new(20, 1));
+        view.state.cursor = 5;
+        view.on_event(Event::CtrlChar('k'));
+        view.state.cursor = 0;
+
+        view.on_event(Event::CtrlChar('y'));
+
+        assert_eq!(&*view.get_content(), " worldhello");
+    }
+
+    #[test]
+    fn alt_u_and_alt_r_undo_and_redo_through_on_event() {
+        // Ctrl-Z/Ctrl-Shift-Z can never reach on_event (the curses
+        // backend only produces CtrlChar('a')..('y')), so undo/redo are
+        // bound to Alt-U/Alt-R; exercise the real bindings, not
+        // state.undo()/state.redo() directly.
+        let mut view = EditView::new();
+        view.layout(Vec2::new(20, 1));
+
+        view.on_event(Event::Char('a'));
+        view.on_event(Event::Char('b'));
+        assert_eq!(&*view.get_content(), "ab");
+
+        view.on_event(Event::AltChar('u'));
+        assert_eq!(&*view.get_content(), "");
+
+        view.on_event(Event::AltChar('r'));
+        assert_eq!(&*view.get_content(), "ab");
+    }
+}
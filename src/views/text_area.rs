@@ -0,0 +1,593 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use std::rc::Rc;
+use std::cmp::min;
+
+use {Cursive, Printer, With};
+use direction::Direction;
+use theme::{ColorStyle, Effect};
+use vec::Vec2;
+use view::View;
+use event::{Callback, Event, EventResult, Key};
+use views::edit_state::EditState;
+
+
+/// Multi-line text editor.
+///
+/// Unlike [`EditView`], which is a single line of text, `TextArea` holds
+/// an arbitrary number of lines, soft-wrapped to the view's width.
+///
+/// [`EditView`]: struct.EditView.html
+///
+/// # Examples
+///
+/// ```
+/// # extern crate cursive;
+/// # use cursive::prelude::*;
+/// # fn main() {
+/// let mut siv = Cursive::new();
+///
+/// siv.add_layer(Dialog::around(TextArea::new().with_id("text"))
+///     .button("Ok", |s| {
+///         let text = s.find_id::<TextArea>("text").unwrap().get_content().to_string();
+///         s.pop_layer();
+///         s.add_layer(Dialog::info(text));
+///     }));
+/// # }
+/// ```
+pub struct TextArea {
+    /// Content, cursor, undo/redo history, kill ring and filter, shared
+    /// with `EditView`.
+    state: EditState,
+
+    /// Each visible, soft-wrapped row, recomputed on layout.
+    rows: Vec<Row>,
+    /// Index of the first visible row.
+    scroll: usize,
+
+    /// Size last given to `layout`.
+    last_size: Vec2,
+
+    /// Number of rows requested by `get_min_size`.
+    min_rows: usize,
+
+    /// Callback when the content is modified.
+    on_edit: Option<Rc<Fn(&mut Cursive, &str, usize)>>,
+
+    enabled: bool,
+}
+
+new_default!(TextArea);
+
+/// A single soft-wrapped visible row: a byte range into the content.
+#[derive(Clone, Copy)]
+struct Row {
+    start: usize,
+    end: usize,
+}
+
+/// Splits `content` into soft-wrapped rows no wider than `width`.
+fn make_rows(content: &str, width: usize) -> Vec<Row> {
+    let mut rows = Vec::new();
+    let mut line_start = 0;
+
+    for line in content.split('\n') {
+        wrap_line(content, line_start, line.len(), width, &mut rows);
+        line_start += line.len() + 1;
+    }
+
+    rows
+}
+
+/// Appends the rows for a single logical line `content[start..start+len]`.
+fn wrap_line(content: &str, start: usize, len: usize, width: usize,
+             rows: &mut Vec<Row>) {
+    let line = &content[start..start + len];
+
+    if line.is_empty() {
+        rows.push(Row {
+            start: start,
+            end: start,
+        });
+        return;
+    }
+
+    let width = if width == 0 { 1 } else { width };
+    let mut row_start = start;
+    let mut row_width = 0;
+    let mut pos = start;
+
+    for g in line.graphemes(true) {
+        let g_width = g.width();
+        if row_width > 0 && row_width + g_width > width {
+            rows.push(Row {
+                start: row_start,
+                end: pos,
+            });
+            row_start = pos;
+            row_width = 0;
+        }
+        row_width += g_width;
+        pos += g.len();
+    }
+
+    rows.push(Row {
+        start: row_start,
+        end: pos,
+    });
+}
+
+/// Finds the index of the row containing `cursor`, for drawing.
+///
+/// Adjacent rows share a boundary byte (`row[n].end == row[n + 1].start`),
+/// so a cursor sitting exactly on a soft wrap is ambiguous. This biases to
+/// the *earlier* row, which is what `draw` needs to print a single cursor
+/// instead of one at the end of the earlier row and another at the start
+/// of the later one.
+fn row_at(rows: &[Row], cursor: usize) -> usize {
+    if rows.is_empty() {
+        return 0;
+    }
+    for (i, row) in rows.iter().enumerate() {
+        if cursor <= row.end {
+            return i;
+        }
+    }
+    rows.len() - 1
+}
+
+/// Finds the index of the row containing `cursor`, for editing/navigation.
+///
+/// Same ambiguity as [`row_at`], but biased to the *later* row: a cursor on
+/// a soft wrap boundary is treated as sitting at the start of the next row,
+/// matching where `Up`/`Down` put it when moving to column 0 of that row.
+/// `End` leaves the cursor on that same boundary (the end of the earlier
+/// row), so the next editing key acts on the later row. Using the draw-only bias
+/// here made `Home`, `Ctrl-K`, `Ctrl-U` and `Down` silently no-op (or jump
+/// to the wrong row) whenever the cursor landed exactly on a wrap point.
+fn row_at_for_edit(rows: &[Row], cursor: usize) -> usize {
+    for (i, row) in rows.iter().enumerate().rev() {
+        if cursor >= row.start {
+            return i;
+        }
+    }
+    0
+}
+
+/// Finds the byte offset in `row` closest to display column `width`.
+fn cursor_at_width(content: &str, row: Row, width: usize) -> usize {
+    let mut w = 0;
+    let mut pos = row.start;
+    for g in content[row.start..row.end].graphemes(true) {
+        let g_width = g.width();
+        if w + g_width > width {
+            return pos;
+        }
+        w += g_width;
+        pos += g.len();
+    }
+    pos
+}
+
+impl TextArea {
+    /// Creates a new, empty `TextArea`.
+    pub fn new() -> Self {
+        TextArea {
+            state: EditState::new(),
+            rows: vec![Row { start: 0, end: 0 }],
+            scroll: 0,
+            last_size: Vec2::new(0, 0),
+            min_rows: 3,
+            on_edit: None,
+            enabled: true,
+        }
+    }
+
+    /// Sets a callback to be called whenever the content is modified.
+    pub fn on_edit<F: Fn(&mut Cursive, &str, usize) + 'static>(mut self,
+                                                               callback: F)
+                                                               -> Self {
+        self.on_edit = Some(Rc::new(callback));
+        self
+    }
+
+    /// Sets a filter to apply to each character before it is inserted.
+    ///
+    /// See [`EditView::filter_char`](struct.EditView.html#method.filter_char).
+    pub fn filter_char<F: Fn(char) -> Option<char> + 'static>(mut self,
+                                                               filter: F)
+                                                               -> Self {
+        self.state.filter = Some(Rc::new(filter));
+        self
+    }
+
+    /// Sets the number of rows requested by `get_min_size`.
+    pub fn min_rows(mut self, min_rows: usize) -> Self {
+        self.min_rows = min_rows;
+        self
+    }
+
+    /// Sets the maximum number of undo snapshots to keep.
+    pub fn max_undo_depth(mut self, max_undo_depth: usize) -> Self {
+        self.state.set_max_undo_depth(max_undo_depth);
+        self
+    }
+
+    /// Disables this view.
+    pub fn disabled(self) -> Self {
+        self.with(|s| s.set_enabled(false))
+    }
+
+    /// Enable or disable this view.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Replace the entire content of the view with the given one.
+    pub fn set_content(&mut self, content: &str) {
+        self.state.content = Rc::new(content.to_string());
+        self.state.cursor = min(self.state.cursor, self.state.content.len());
+    }
+
+    /// Get the current text.
+    pub fn get_content(&self) -> Rc<String> {
+        self.state.content.clone()
+    }
+
+    /// Sets the current content to the given value.
+    ///
+    /// Convenient chainable method.
+    pub fn content(mut self, content: &str) -> Self {
+        self.set_content(content);
+        self
+    }
+
+    /// Undoes the last edit, restoring the previous content and cursor.
+    pub fn undo(&mut self) {
+        self.state.undo();
+    }
+
+    /// Redoes the last undone edit.
+    pub fn redo(&mut self) {
+        self.state.redo();
+    }
+
+    /// Insert `ch` at the current cursor position.
+    pub fn insert(&mut self, ch: char) {
+        self.state.insert(ch);
+    }
+
+    /// Remove `len` bytes starting at the current cursor position.
+    pub fn remove(&mut self, len: usize) {
+        self.state.remove(len);
+    }
+
+    fn scroll_to_cursor(&mut self) {
+        let row = row_at(&self.rows, self.state.cursor);
+        let height = self.last_size.y;
+        if height == 0 {
+            return;
+        }
+        if row < self.scroll {
+            self.scroll = row;
+        } else if row >= self.scroll + height {
+            self.scroll = row + 1 - height;
+        }
+    }
+}
+
+impl View for TextArea {
+    fn draw(&self, printer: &Printer) {
+        printer.with_color(ColorStyle::Secondary, |printer| {
+            let effect = if self.enabled {
+                Effect::Reverse
+            } else {
+                Effect::Simple
+            };
+
+            // Adjacent rows share a boundary byte (row[n].end ==
+            // row[n+1].start), so resolve the cursor to a single row up
+            // front instead of testing each row's range independently.
+            let cursor_row = row_at(&self.rows, self.state.cursor);
+
+            for (i, row) in self.rows
+                .iter()
+                .enumerate()
+                .skip(self.scroll)
+                .take(printer.size.y) {
+
+                let y = i - self.scroll;
+                let line = &self.state.content[row.start..row.end];
+
+                printer.with_effect(effect, |printer| {
+                    printer.print((0, y), line);
+                });
+
+                if printer.focused && i == cursor_row {
+                    let offset =
+                        self.state.content[row.start..self.state.cursor].width();
+                    let c: &str = if self.state.cursor == row.end {
+                        "_"
+                    } else {
+                        self.state.content[self.state.cursor..]
+                            .graphemes(true)
+                            .next()
+                            .unwrap()
+                    };
+                    printer.print((offset, y), c);
+                }
+            }
+        });
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        self.last_size = size;
+        self.rows = make_rows(&self.state.content, size.x);
+        self.scroll_to_cursor();
+    }
+
+    fn get_min_size(&mut self, constraint: Vec2) -> Vec2 {
+        Vec2::new(constraint.x, self.min_rows)
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool {
+        self.enabled
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        let extend_kill = self.state.continuing_kill;
+        self.state.continuing_kill = false;
+
+        match event {
+            Event::Char(ch) => {
+                let ch = match self.state.filter {
+                    None => Some(ch),
+                    Some(ref filter) => filter(ch),
+                };
+
+                match ch {
+                    None => return EventResult::Consumed(None),
+                    Some(ch) => self.insert(ch),
+                }
+            }
+            Event::Key(Key::Enter) => self.insert('\n'),
+            Event::CtrlChar('a') => {
+                let row = row_at_for_edit(&self.rows, self.state.cursor);
+                self.state.cursor = self.rows[row].start;
+            }
+            Event::CtrlChar('e') => {
+                let row = row_at_for_edit(&self.rows, self.state.cursor);
+                self.state.cursor = self.rows[row].end;
+            }
+            Event::CtrlChar('k') => {
+                let row = row_at_for_edit(&self.rows, self.state.cursor);
+                let killed = self.state.content[self.state.cursor..self.rows[row].end]
+                    .to_string();
+                if extend_kill {
+                    self.state.kill_buffer.push_str(&killed);
+                } else {
+                    self.state.kill_buffer = killed;
+                }
+                let len = self.rows[row].end - self.state.cursor;
+                self.remove(len);
+                self.state.continuing_kill = true;
+            }
+            Event::CtrlChar('u') => {
+                let row = row_at_for_edit(&self.rows, self.state.cursor);
+                let start = self.rows[row].start;
+                self.state.kill_buffer = self.state.content[start..self.state.cursor]
+                    .to_string();
+                let len = self.state.cursor - start;
+                self.state.cursor = start;
+                self.remove(len);
+            }
+            Event::CtrlChar('y') => {
+                let text = self.state.kill_buffer.clone();
+                self.state.insert_str(&text);
+            }
+            // See the matching comment in EditView::on_event: Ctrl-Z/
+            // Ctrl-Shift-Z can't reach us through the curses backend, so
+            // undo/redo are bound to Alt-U/Alt-R instead.
+            Event::AltChar('u') => self.undo(),
+            Event::AltChar('r') => self.redo(),
+            Event::Key(Key::Home) => {
+                let row = row_at_for_edit(&self.rows, self.state.cursor);
+                self.state.cursor = self.rows[row].start;
+            }
+            Event::Key(Key::End) => {
+                let row = row_at_for_edit(&self.rows, self.state.cursor);
+                self.state.cursor = self.rows[row].end;
+            }
+            Event::Key(Key::Left) if self.state.cursor > 0 => {
+                let len = self.state.content[..self.state.cursor]
+                    .graphemes(true)
+                    .last()
+                    .unwrap()
+                    .len();
+                self.state.cursor -= len;
+            }
+            Event::Key(Key::Right) if self.state.cursor < self.state.content.len() => {
+                let len = self.state.content[self.state.cursor..]
+                    .graphemes(true)
+                    .next()
+                    .unwrap()
+                    .len();
+                self.state.cursor += len;
+            }
+            Event::Key(Key::Up) => {
+                let row = row_at_for_edit(&self.rows, self.state.cursor);
+                if row > 0 {
+                    let col = self.state.content[self.rows[row].start..self.state.cursor]
+                        .width();
+                    self.state.cursor =
+                        cursor_at_width(&self.state.content, self.rows[row - 1], col);
+                }
+            }
+            Event::Key(Key::Down) => {
+                let row = row_at_for_edit(&self.rows, self.state.cursor);
+                if row + 1 < self.rows.len() {
+                    let col = self.state.content[self.rows[row].start..self.state.cursor]
+                        .width();
+                    self.state.cursor =
+                        cursor_at_width(&self.state.content, self.rows[row + 1], col);
+                }
+            }
+            Event::Key(Key::PageUp) => {
+                let row = row_at_for_edit(&self.rows, self.state.cursor);
+                let col = self.state.content[self.rows[row].start..self.state.cursor]
+                    .width();
+                let height = self.last_size.y.max(1);
+                let target = row.saturating_sub(height);
+                self.state.cursor = cursor_at_width(&self.state.content,
+                                                     self.rows[target],
+                                                     col);
+            }
+            Event::Key(Key::PageDown) => {
+                let row = row_at_for_edit(&self.rows, self.state.cursor);
+                let col = self.state.content[self.rows[row].start..self.state.cursor]
+                    .width();
+                let height = self.last_size.y.max(1);
+                let target = min(row + height, self.rows.len() - 1);
+                self.state.cursor = cursor_at_width(&self.state.content,
+                                                     self.rows[target],
+                                                     col);
+            }
+            Event::Key(Key::Backspace) if self.state.cursor > 0 => {
+                let len = self.state.content[..self.state.cursor]
+                    .graphemes(true)
+                    .last()
+                    .unwrap()
+                    .len();
+                self.state.cursor -= len;
+                self.remove(len);
+            }
+            Event::Key(Key::Del) if self.state.cursor < self.state.content.len() => {
+                let len = self.state.content[self.state.cursor..]
+                    .graphemes(true)
+                    .next()
+                    .unwrap()
+                    .len();
+                self.remove(len);
+            }
+            _ => return EventResult::Ignored,
+        }
+
+        self.rows = make_rows(&self.state.content, self.last_size.x);
+        self.scroll_to_cursor();
+
+        let cb = self.on_edit.clone().map(|cb| {
+            let content = self.state.content.clone();
+            let cursor = self.state.cursor;
+
+            Callback::from_fn(move |s| {
+                cb(s, &content, cursor);
+            })
+        });
+        EventResult::Consumed(cb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use event::{Event, Key};
+
+    #[test]
+    fn make_rows_wraps_at_width() {
+        let rows = make_rows("abcdefghij", 5);
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].start, rows[0].end), (0, 5));
+        assert_eq!((rows[1].start, rows[1].end), (5, 10));
+    }
+
+    #[test]
+    fn make_rows_one_row_per_newline_separated_line() {
+        let rows = make_rows("ab\ncd", 5);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&"ab\ncd"[rows[0].start..rows[0].end], "ab");
+        assert_eq!(&"ab\ncd"[rows[1].start..rows[1].end], "cd");
+    }
+
+    #[test]
+    fn cursor_at_width_finds_matching_column() {
+        let rows = make_rows("abcdefghij", 5);
+        assert_eq!(cursor_at_width("abcdefghij", rows[1], 2), 7);
+        assert_eq!(cursor_at_width("abcdefghij", rows[1], 0), 5);
+    }
+
+    #[test]
+    fn row_at_biases_earlier_row_on_wrap_boundary() {
+        // A cursor sitting exactly on the wrap boundary between two rows
+        // (byte 5) belongs to row 0 for drawing purposes, so only one
+        // cursor is ever printed.
+        let rows = make_rows("abcdefghij", 5);
+        assert_eq!(row_at(&rows, 5), 0);
+        assert_eq!(row_at(&rows, 0), 0);
+        assert_eq!(row_at(&rows, 10), 1);
+    }
+
+    #[test]
+    fn row_at_for_edit_biases_later_row_on_wrap_boundary() {
+        let rows = make_rows("abcdefghij", 5);
+        assert_eq!(row_at_for_edit(&rows, 5), 1);
+        assert_eq!(row_at_for_edit(&rows, 0), 0);
+        assert_eq!(row_at_for_edit(&rows, 10), 1);
+    }
+
+    #[test]
+    fn ctrl_k_kills_to_end_of_row_from_wrap_boundary() {
+        // Regression test: with the cursor sitting exactly on a soft-wrap
+        // boundary, Ctrl-K used to be a silent no-op because the kill
+        // range was computed against the earlier (draw-only) row.
+        let mut area = TextArea::new().content("abcdefghij");
+        area.layout(Vec2::new(5, 3));
+        area.state.cursor = 5;
+
+        area.on_event(Event::CtrlChar('k'));
+
+        assert_eq!(&*area.get_content(), "abcde");
+        assert_eq!(area.state.kill_buffer, "fghij");
+    }
+
+    #[test]
+    fn home_is_a_no_op_at_the_start_of_a_wrapped_row() {
+        let mut area = TextArea::new().content("abcdefghij");
+        area.layout(Vec2::new(5, 3));
+        area.state.cursor = 5;
+
+        area.on_event(Event::Key(Key::Home));
+
+        assert_eq!(area.state.cursor, 5);
+    }
+
+    #[test]
+    fn down_does_nothing_from_the_last_wrapped_row() {
+        let mut area = TextArea::new().content("abcdefghij");
+        area.layout(Vec2::new(5, 3));
+        area.state.cursor = 5;
+
+        area.on_event(Event::Key(Key::Down));
+
+        assert_eq!(area.state.cursor, 5);
+    }
+
+    #[test]
+    fn alt_u_and_alt_r_undo_and_redo_through_on_event() {
+        // See the matching comment in EditView::on_event: Ctrl-Z/
+        // Ctrl-Shift-Z can never reach on_event, so exercise the real
+        // Alt-U/Alt-R bindings instead of state.undo()/state.redo().
+        let mut area = TextArea::new();
+        area.layout(Vec2::new(20, 3));
+
+        area.on_event(Event::Char('a'));
+        area.on_event(Event::Char('b'));
+        assert_eq!(&*area.get_content(), "ab");
+
+        area.on_event(Event::AltChar('u'));
+        assert_eq!(&*area.get_content(), "");
+
+        area.on_event(Event::AltChar('r'));
+        assert_eq!(&*area.get_content(), "ab");
+    }
+}